@@ -1,16 +1,21 @@
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Read, Write};
 use std::thread;
 use std::sync::Arc;
+use std::path::Path;
 use uuid::Uuid;
-use rand::RngCore;
+use rand::{Rng, RngCore};
 use num_cpus;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Mutex;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use lazy_static::lazy_static;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Semaphore;
+use std::time::{Duration, Instant};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
@@ -22,22 +27,581 @@ struct Args {
 	// Randomized buffer size in MB
 	#[arg(short, long, default_value = "100")]
 	buffer_size: usize,
+
+	// Minimum free space to preserve on the target volume, e.g. "500MB" or "10%"
+	#[arg(long, default_value = "5%")]
+	min_free: MinFree,
+
+	// Flip STOP_SIGNAL (and wind the whole pool down) once free space drops below --min-free,
+	// instead of just pausing the thread that noticed
+	#[arg(long, default_value_t = false)]
+	stop_on_low_space: bool,
+
+	// Execution backend: a thread per core doing blocking I/O, or a tokio runtime
+	// with a fixed number of in-flight async file operations
+	#[arg(long, value_enum, default_value_t = Mode::Blocking)]
+	mode: Mode,
+
+	// Max number of in-flight file operations when --mode async (ignored in blocking mode)
+	#[arg(long, default_value = "256")]
+	concurrency: usize,
+
+	// How often, in seconds, to print rolling throughput/IOPS/latency stats
+	#[arg(long, default_value = "5")]
+	report_interval: u64,
+
+	// Reopen and read back each file after writing it, failing loudly on a mismatch
+	#[arg(long, default_value_t = false)]
+	verify: bool,
+
+	// Fraction (0.0-1.0) of loop iterations that read back an existing tracked file
+	// instead of writing a new one
+	#[arg(long, default_value = "0.0")]
+	read_ratio: f64,
+
+	// Shape of the data written to each file
+	#[arg(long, value_enum, default_value_t = Pattern::Random)]
+	pattern: Pattern,
+
+	// Compress the buffer before writing it, to compare throughput on compressible
+	// vs. incompressible payloads
+	#[arg(long, value_enum)]
+	compress: Option<Compress>,
+
+	// Stream the file to a total size larger than --buffer-size, in fixed-size chunks,
+	// instead of holding the whole file in memory at once
+	#[arg(long)]
+	file_size: Option<FileSize>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Pattern {
+	Random,
+	Zero,
+	Text,
+	Repeating,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Compress {
+	Lz4,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Mode {
+	Blocking,
+	Async,
+}
+
+// Bundles the options `disk_thrash`/`disk_thrash_async` need beyond the file's
+// parent directory and content, so adding a knob doesn't grow their argument list.
+#[derive(Debug, Clone, Copy)]
+struct WriteOptions {
+	min_free: MinFree,
+	stop_on_low_space: bool,
+	verify: bool,
+	compress: Option<Compress>,
+	file_size: Option<FileSize>,
+	pattern: Pattern,
+}
+
+impl WriteOptions {
+	fn from_args(args: &Args) -> Self {
+		WriteOptions {
+			min_free: args.min_free,
+			stop_on_low_space: args.stop_on_low_space,
+			verify: args.verify,
+			compress: args.compress,
+			file_size: args.file_size,
+			pattern: args.pattern,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MinFree {
+	Bytes(u64),
+	Percent(f64),
+}
+
+// Parses sizes like "512", "500KB", "10GB", "2TB" (1024-based) into a byte count.
+fn parse_byte_size(s: &str) -> Result<u64, String> {
+	let lower = s.trim().to_lowercase();
+	let (num, multiplier) = if let Some(n) = lower.strip_suffix("tb") {
+		(n, 1024u64 * 1024 * 1024 * 1024)
+	} else if let Some(n) = lower.strip_suffix("gb") {
+		(n, 1024u64 * 1024 * 1024)
+	} else if let Some(n) = lower.strip_suffix("mb") {
+		(n, 1024u64 * 1024)
+	} else if let Some(n) = lower.strip_suffix("kb") {
+		(n, 1024u64)
+	} else if let Some(n) = lower.strip_suffix('b') {
+		(n, 1)
+	} else {
+		(lower.as_str(), 1)
+	};
+
+	let num: u64 = num
+		.trim()
+		.parse()
+		.map_err(|_| format!("invalid size in '{}'", s))?;
+
+	Ok(num * multiplier)
+}
+
+impl FromStr for MinFree {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+
+		if let Some(pct) = s.strip_suffix('%') {
+			let pct: f64 = pct
+				.trim()
+				.parse()
+				.map_err(|_| format!("invalid percentage in '{}'", s))?;
+			if !(0.0..=100.0).contains(&pct) {
+				return Err(format!("percentage must be between 0 and 100, got {}", pct));
+			}
+			return Ok(MinFree::Percent(pct));
+		}
+
+		Ok(MinFree::Bytes(parse_byte_size(s)?))
+	}
+}
+
+// Total size of the file to produce via the streaming writer, independent of
+// --buffer-size which becomes the per-chunk size in that mode.
+#[derive(Debug, Clone, Copy)]
+struct FileSize(u64);
+
+impl FromStr for FileSize {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(FileSize(parse_byte_size(s)?))
+	}
+}
+
+// Tracks what each writer-in-flight file actually contains on disk, so a concurrent
+// reader can compare against the real bytes (e.g. compressed, for `--compress`)
+// rather than assuming the shared uncompressed buffer. `None` for streamed files,
+// whose full content isn't kept in memory.
 lazy_static! {
-	static ref CREATED_FILES: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
+	static ref CREATED_FILES: Mutex<HashMap<PathBuf, Option<Arc<Vec<u8>>>>> = Mutex::new(HashMap::new());
 }
 
 static STOP_SIGNAL: AtomicBool = AtomicBool::new(false);
 
-fn disk_thrash(parent_dir: &PathBuf, buffer: &[u8]) -> std::io::Result<()> {
+// Latency is bucketed by power-of-two microsecond boundaries (1us, 2us, 4us, ... ~2147s),
+// which is cheap to update from many threads and precise enough for p50/p99 reporting.
+const NUM_LATENCY_BUCKETS: usize = 32;
+
+struct Metrics {
+	bytes_written: AtomicU64,
+	files_completed: AtomicU64,
+	latency_buckets_us: [AtomicU64; NUM_LATENCY_BUCKETS],
+}
+
+impl Metrics {
+	fn new() -> Self {
+		Metrics {
+			bytes_written: AtomicU64::new(0),
+			files_completed: AtomicU64::new(0),
+			latency_buckets_us: std::array::from_fn(|_| AtomicU64::new(0)),
+		}
+	}
+
+	fn record(&self, bytes: u64, latency: Duration) {
+		self.bytes_written.fetch_add(bytes, Ordering::Relaxed);
+		self.files_completed.fetch_add(1, Ordering::Relaxed);
+
+		let micros = latency.as_micros().max(1) as u64;
+		let bucket = (63 - micros.leading_zeros() as usize).min(NUM_LATENCY_BUCKETS - 1);
+		self.latency_buckets_us[bucket].fetch_add(1, Ordering::Relaxed);
+	}
+
+	// Approximate percentile: returns the lower bound (in microseconds) of the bucket
+	// that `pct` falls into.
+	fn percentile(&self, pct: f64) -> u64 {
+		let counts: Vec<u64> = self
+			.latency_buckets_us
+			.iter()
+			.map(|b| b.load(Ordering::Relaxed))
+			.collect();
+		let total: u64 = counts.iter().sum();
+		if total == 0 {
+			return 0;
+		}
+
+		let target = ((total as f64) * pct / 100.0).ceil() as u64;
+		let mut running = 0u64;
+		for (i, count) in counts.iter().enumerate() {
+			running += count;
+			if running >= target {
+				return 1u64 << i;
+			}
+		}
+
+		1u64 << (NUM_LATENCY_BUCKETS - 1)
+	}
+}
+
+lazy_static! {
+	static ref METRICS: Metrics = Metrics::new();
+}
+
+fn spawn_reporter(report_interval: u64) -> thread::JoinHandle<()> {
+	thread::spawn(move || {
+		let mut last_bytes = METRICS.bytes_written.load(Ordering::Relaxed);
+		let mut last_files = METRICS.files_completed.load(Ordering::Relaxed);
+		let mut last_tick = Instant::now();
+
+		while !STOP_SIGNAL.load(Ordering::SeqCst) {
+			thread::sleep(Duration::from_secs(report_interval));
+
+			let bytes = METRICS.bytes_written.load(Ordering::Relaxed);
+			let files = METRICS.files_completed.load(Ordering::Relaxed);
+			let elapsed = last_tick.elapsed().as_secs_f64();
+
+			let throughput_mb_s = (bytes.saturating_sub(last_bytes) as f64 / (1024.0 * 1024.0)) / elapsed;
+			let files_per_sec = files.saturating_sub(last_files) as f64 / elapsed;
+
+			println!(
+				"[metrics] {:.2} MB/s, {:.2} files/s, p50={}us, p99={}us",
+				throughput_mb_s,
+				files_per_sec,
+				METRICS.percentile(50.0),
+				METRICS.percentile(99.0),
+			);
+
+			last_bytes = bytes;
+			last_files = files;
+			last_tick = Instant::now();
+		}
+	})
+}
+
+fn print_final_summary() {
+	let bytes = METRICS.bytes_written.load(Ordering::Relaxed);
+	let files = METRICS.files_completed.load(Ordering::Relaxed);
+	println!(
+		"Final summary: {} files written, {:.2} MB total, p50={}us, p99={}us",
+		files,
+		bytes as f64 / (1024.0 * 1024.0),
+		METRICS.percentile(50.0),
+		METRICS.percentile(99.0),
+	);
+}
+
+// Returns (available_bytes, total_bytes) for the filesystem backing `path`.
+#[cfg(unix)]
+fn disk_space(path: &Path) -> std::io::Result<(u64, u64)> {
+	use std::ffi::CString;
+	use std::mem::MaybeUninit;
+	use std::os::unix::ffi::OsStrExt;
+
+	let c_path = CString::new(path.as_os_str().as_bytes())
+		.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+	unsafe {
+		let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+		if libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
+			return Err(std::io::Error::last_os_error());
+		}
+		let stat = stat.assume_init();
+		let available = stat.f_bavail as u64 * stat.f_frsize as u64;
+		let total = stat.f_blocks as u64 * stat.f_frsize as u64;
+		Ok((available, total))
+	}
+}
+
+#[cfg(windows)]
+fn disk_space(path: &Path) -> std::io::Result<(u64, u64)> {
+	use std::os::windows::ffi::OsStrExt;
+	use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+	let mut wide: Vec<u16> = path.as_os_str().encode_wide().collect();
+	wide.push(0);
+
+	let mut free_bytes_available: u64 = 0;
+	let mut total_bytes: u64 = 0;
+	let mut total_free_bytes: u64 = 0;
+
+	let ok = unsafe {
+		GetDiskFreeSpaceExW(
+			wide.as_ptr(),
+			&mut free_bytes_available,
+			&mut total_bytes,
+			&mut total_free_bytes,
+		)
+	};
+
+	if ok == 0 {
+		return Err(std::io::Error::last_os_error());
+	}
+
+	Ok((free_bytes_available, total_bytes))
+}
+
+// Pure byte/percentage math behind `has_room`, split out so it can be unit tested
+// without touching the filesystem.
+fn meets_min_free(available_after: u64, total: u64, min_free: MinFree) -> bool {
+	match min_free {
+		MinFree::Bytes(min) => available_after >= min,
+		MinFree::Percent(pct) => {
+			let available_pct = if total == 0 {
+				0.0
+			} else {
+				(available_after as f64 / total as f64) * 100.0
+			};
+			available_pct >= pct
+		}
+	}
+}
+
+// Checks whether `pending_bytes` can be written to `parent_dir` while still leaving
+// at least `min_free` free afterwards, not just whether `min_free` is met right now.
+fn has_room(parent_dir: &Path, min_free: MinFree, pending_bytes: u64) -> std::io::Result<bool> {
+	let (available, total) = disk_space(parent_dir)?;
+	let available_after = available.saturating_sub(pending_bytes);
+
+	Ok(meets_min_free(available_after, total, min_free))
+}
+
+// `has_room` does a blocking `statvfs`/`GetDiskFreeSpaceExW` syscall, so the async
+// code paths run it on a blocking-pool thread instead of the tokio reactor, same as
+// any other blocking I/O call from async code.
+async fn has_room_async(parent_dir: &Path, min_free: MinFree, pending_bytes: u64) -> std::io::Result<bool> {
+	let parent_dir = parent_dir.to_path_buf();
+	tokio::task::spawn_blocking(move || has_room(&parent_dir, min_free, pending_bytes))
+		.await
+		.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?
+}
+
+// Fills `chunk`'s spare capacity directly (no zero-init pass first) and sets its
+// length to `len`, reusing the same allocation across calls so large-file streaming
+// doesn't pay for a fresh multi-MB Vec every chunk.
+fn fill_spare_capacity(chunk: &mut Vec<u8>, len: usize, pattern: Pattern, rng: &mut impl RngCore) {
+	chunk.clear();
+	chunk.reserve(len);
+	let spare = &mut chunk.spare_capacity_mut()[..len];
+
+	match pattern {
+		Pattern::Zero => {
+			for slot in spare.iter_mut() {
+				slot.write(0);
+			}
+		}
+		Pattern::Random => {
+			for slot in spare.iter_mut() {
+				slot.write(rng.next_u32() as u8);
+			}
+		}
+		Pattern::Text | Pattern::Repeating => {
+			let unit: &[u8] = if pattern == Pattern::Text {
+				b"The quick brown fox jumps over the lazy dog. "
+			} else {
+				&[0xDE, 0xAD, 0xBE, 0xEF]
+			};
+			for (slot, byte) in spare.iter_mut().zip(unit.iter().cycle()) {
+				slot.write(*byte);
+			}
+		}
+	}
+
+	// Safety: the loop above just initialized every one of the first `len` bytes.
+	unsafe {
+		chunk.set_len(len);
+	}
+}
+
+// How often (in chunks) a streamed write rechecks free space. A single streamed
+// write can be hundreds of GB, so the once-up-front check in `disk_thrash` isn't
+// enough to stop it from wedging the host mid-stream.
+const SPACE_CHECK_INTERVAL_CHUNKS: u32 = 16;
+
+// Streams `total_size` logical bytes to `file` in chunks of at most `chunk_size`,
+// through a BufWriter, without ever holding the full file contents in memory at
+// once. If `compress` is set, each chunk is compressed independently (sender-pays,
+// same as the non-streaming path) before being written, so `total_size` tracks
+// uncompressed bytes generated while the returned count is the actual bytes
+// written to disk. Rechecks free space every `SPACE_CHECK_INTERVAL_CHUNKS` chunks
+// and, if `parent_dir` has dropped below `min_free`, stops early and returns the
+// bytes written so far rather than continuing to fill the disk.
+fn write_streamed(
+	file: File,
+	total_size: u64,
+	chunk_size: usize,
+	parent_dir: &Path,
+	opts: &WriteOptions,
+) -> std::io::Result<u64> {
+	let mut writer = BufWriter::new(file);
+	let mut chunk = Vec::with_capacity(chunk_size);
+	let mut rng = rand::rng();
+	let mut generated: u64 = 0;
+	let mut written: u64 = 0;
+	let mut chunks_since_check: u32 = 0;
+
+	while generated < total_size {
+		let this_chunk_len = ((total_size - generated) as usize).min(chunk_size);
+		fill_spare_capacity(&mut chunk, this_chunk_len, opts.pattern, &mut rng);
+		generated += this_chunk_len as u64;
+
+		let to_write: std::borrow::Cow<[u8]> = match opts.compress {
+			Some(Compress::Lz4) => std::borrow::Cow::Owned(lz4_compress(&chunk)?),
+			None => std::borrow::Cow::Borrowed(&chunk),
+		};
+		writer.write_all(&to_write)?;
+		written += to_write.len() as u64;
+
+		chunks_since_check += 1;
+		if chunks_since_check >= SPACE_CHECK_INTERVAL_CHUNKS {
+			chunks_since_check = 0;
+			if !has_room(parent_dir, opts.min_free, chunk_size as u64)? {
+				println!(
+					"Low free space on {}, truncating streamed write at {} of {} bytes generated",
+					parent_dir.display(),
+					generated,
+					total_size
+				);
+				if opts.stop_on_low_space {
+					STOP_SIGNAL.store(true, Ordering::SeqCst);
+				}
+				break;
+			}
+		}
+	}
+
+	writer.flush()?;
+	writer.get_ref().sync_all()?;
+
+	Ok(written)
+}
+
+fn generate_buffer(pattern: Pattern, size: usize) -> Vec<u8> {
+	match pattern {
+		Pattern::Random => {
+			let mut buffer = vec![0u8; size];
+			rand::rng().fill_bytes(&mut buffer);
+			buffer
+		}
+		Pattern::Zero => vec![0u8; size],
+		Pattern::Text => {
+			const LOREM: &[u8] = b"The quick brown fox jumps over the lazy dog. ";
+			LOREM.iter().cycle().take(size).copied().collect()
+		}
+		Pattern::Repeating => {
+			const UNIT: &[u8] = &[0xDE, 0xAD, 0xBE, 0xEF];
+			UNIT.iter().cycle().take(size).copied().collect()
+		}
+	}
+}
+
+// Compression is CPU work, so it's done per-iteration on the thread that owns the
+// write (sender-pays) rather than once up front on the shared buffer.
+fn lz4_compress(buffer: &[u8]) -> std::io::Result<Vec<u8>> {
+	let mut encoder = lz4::EncoderBuilder::new().build(Vec::new())?;
+	encoder.write_all(buffer)?;
+	let (compressed, result) = encoder.finish();
+	result?;
+	Ok(compressed)
+}
+
+// Reopens `filename` with its own handle/cursor and compares its full contents
+// against `expected`, so a reader never shares state with (or blocks) the writer.
+fn verify_contents(filename: &Path, expected: &[u8]) -> std::io::Result<()> {
+	let mut file = File::open(filename)?;
+	let mut contents = Vec::with_capacity(expected.len());
+	file.read_to_end(&mut contents)?;
+
+	if contents != expected {
+		return Err(std::io::Error::new(
+			std::io::ErrorKind::InvalidData,
+			format!(
+				"Verification mismatch for {}: read {} bytes, expected {} bytes",
+				filename.display(),
+				contents.len(),
+				expected.len()
+			),
+		));
+	}
+
+	Ok(())
+}
+
+// How long a reader backs off when no writer currently has a tracked file to read,
+// so an early/low-free-space run doesn't spin on the CREATED_FILES lock.
+const NO_TRACKED_FILES_BACKOFF: Duration = Duration::from_millis(50);
+
+// Picks a random file that some writer currently has tracked in CREATED_FILES and
+// reads it back, modelling a concurrent reader against a single writer per file.
+fn read_tracked_file() -> std::io::Result<()> {
+	let candidate = {
+		let files = CREATED_FILES.lock().unwrap();
+		if files.is_empty() {
+			None
+		} else {
+			let idx = rand::rng().random_range(0..files.len());
+			files.iter().nth(idx).map(|(name, expected)| (name.clone(), expected.clone()))
+		}
+	};
+
+	let Some((filename, expected)) = candidate else {
+		thread::sleep(NO_TRACKED_FILES_BACKOFF);
+		return Ok(());
+	};
+
+	// Streamed files don't keep their full content in memory, so there's nothing to
+	// compare a read-back against. Back off the same as an empty CREATED_FILES map,
+	// since otherwise a run with streamed writes only would spin readers on the lock.
+	let Some(expected) = expected else {
+		thread::sleep(NO_TRACKED_FILES_BACKOFF);
+		return Ok(());
+	};
+
+	let mut file = match File::open(&filename) {
+		Ok(file) => file,
+		// The writer may have removed the file between selection and open.
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+		Err(e) => return Err(e),
+	};
+
+	let mut contents = Vec::with_capacity(expected.len());
+	file.read_to_end(&mut contents)?;
+	println!("Read back {} bytes from {}", contents.len(), filename.display());
+
+	if contents != *expected {
+		eprintln!(
+			"Error: read-back mismatch for {}: read {} bytes, expected {} bytes",
+			filename.display(),
+			contents.len(),
+			expected.len()
+		);
+	}
+
+	Ok(())
+}
+
+fn disk_thrash(parent_dir: &PathBuf, buffer: &[u8], opts: WriteOptions) -> std::io::Result<()> {
+	let pending_bytes = opts.file_size.map_or(buffer.len() as u64, |FileSize(total)| total);
+	if !has_room(parent_dir, opts.min_free, pending_bytes)? {
+		println!("Low free space on {}, refusing to write", parent_dir.display());
+		if opts.stop_on_low_space {
+			STOP_SIGNAL.store(true, Ordering::SeqCst);
+		}
+		return Ok(());
+	}
+
 	let filename = parent_dir.join(format!("{}.tmp", Uuid::new_v4()));
 
 	{
-		CREATED_FILES.lock().unwrap().insert(filename.clone());
+		// Content isn't known yet; filled in below once it's written (streamed
+		// files stay `None`, since their full content isn't kept in memory).
+		CREATED_FILES.lock().unwrap().insert(filename.clone(), None);
 	}
 
-	let mut file = File::create(&filename)?;
+	let file = File::create(&filename)?;
 	println!("Writing to file: {}", filename.display());
 
 	// Check if buffer is empty
@@ -48,20 +612,60 @@ fn disk_thrash(parent_dir: &PathBuf, buffer: &[u8]) -> std::io::Result<()> {
 		));
 	}
 
-	// Write the buffer to the file
-	file.write_all(buffer)?;
-	println!("Finished writing to file: {}", filename.display());
+	let (written, verify_result) = if let Some(FileSize(total_size)) = opts.file_size {
+		if opts.verify {
+			println!("Verification is not supported with --file-size streaming, skipping");
+		}
+
+		let op_start = Instant::now();
+		let written = write_streamed(file, total_size, buffer.len(), parent_dir, &opts)?;
+		METRICS.record(written, op_start.elapsed());
+		println!("File sync complete for: {}", filename.display());
+		(written, Ok(()))
+	} else {
+		let mut file = file;
+
+		let to_write: std::borrow::Cow<[u8]> = match opts.compress {
+			Some(Compress::Lz4) => std::borrow::Cow::Owned(lz4_compress(buffer)?),
+			None => std::borrow::Cow::Borrowed(buffer),
+		};
 
-	// Ensure data is flushed to disk
-	file.sync_all()?;
-	println!("File sync complete for: {}", filename.display());
+		// Write the buffer to the file
+		let op_start = Instant::now();
+		file.write_all(&to_write)?;
+		println!("Finished writing to file: {}", filename.display());
+
+		// Ensure data is flushed to disk
+		file.sync_all()?;
+		METRICS.record(to_write.len() as u64, op_start.elapsed());
+		println!("File sync complete for: {}", filename.display());
+
+		// A mismatch is the expected-to-happen case this tool exists to surface, not
+		// an exceptional one, so it must not skip the cleanup below (else the file
+		// and its CREATED_FILES entry leak for the rest of the run).
+		let verify_result = if opts.verify {
+			verify_contents(&filename, &to_write)
+		} else {
+			Ok(())
+		};
+		if let Err(e) = &verify_result {
+			eprintln!("Error: {}", e);
+		}
+
+		let written = to_write.len() as u64;
+		if let Some(expected) = CREATED_FILES.lock().unwrap().get_mut(&filename) {
+			*expected = Some(Arc::new(to_write.into_owned()));
+		}
+
+		(written, verify_result)
+	};
 
 	let metadata = std::fs::metadata(&filename)?;
-	if metadata.len() != buffer.len() as u64 {
+	if metadata.len() != written {
 		eprintln!(
 			"Error: File did not write the expected size: {} bytes written, expected {} bytes.",
 			metadata.len(),
-			buffer.len()
+			written
 		);
 	}
 
@@ -75,38 +679,259 @@ fn disk_thrash(parent_dir: &PathBuf, buffer: &[u8]) -> std::io::Result<()> {
 		CREATED_FILES.lock().unwrap().remove(&filename);
 	}
 
+	verify_result?;
+
 	Ok(())
 }
 
-fn main() {
-	let args = Args::parse();
+// Mirrors `write_streamed`'s periodic `has_room` recheck and per-chunk compression,
+// so a streamed async write gets the same free-space and `--compress` behavior.
+async fn write_streamed_async(
+	file: tokio::fs::File,
+	total_size: u64,
+	chunk_size: usize,
+	parent_dir: &Path,
+	opts: &WriteOptions,
+) -> std::io::Result<u64> {
+	let mut writer = tokio::io::BufWriter::new(file);
+	let mut chunk = Vec::with_capacity(chunk_size);
+	let mut generated: u64 = 0;
+	let mut written: u64 = 0;
+	let mut chunks_since_check: u32 = 0;
 
-	ctrlc::set_handler(|| {
-		println!("CTRL+C received, stopping...");
-		STOP_SIGNAL.store(true, Ordering::SeqCst);
-	}).expect("Failed to set Ctrl-C handler");
+	while generated < total_size {
+		let this_chunk_len = ((total_size - generated) as usize).min(chunk_size);
+		// A fresh ThreadRng handle per chunk keeps it from being held across the
+		// `.await` below, which would otherwise make this future non-Send.
+		fill_spare_capacity(&mut chunk, this_chunk_len, opts.pattern, &mut rand::rng());
+		generated += this_chunk_len as u64;
 
-	let size = args.buffer_size * 1024 * 1024;
-	let mut buffer = vec![0u8; size];
+		let to_write: std::borrow::Cow<[u8]> = match opts.compress {
+			Some(Compress::Lz4) => std::borrow::Cow::Owned(lz4_compress(&chunk)?),
+			None => std::borrow::Cow::Borrowed(&chunk),
+		};
+		writer.write_all(&to_write).await?;
+		written += to_write.len() as u64;
 
-	let mut rng = rand::rng();
-	rng.fill_bytes(&mut buffer);
+		chunks_since_check += 1;
+		if chunks_since_check >= SPACE_CHECK_INTERVAL_CHUNKS {
+			chunks_since_check = 0;
+			if !has_room_async(parent_dir, opts.min_free, chunk_size as u64).await? {
+				println!(
+					"Low free space on {}, truncating streamed write at {} of {} bytes generated",
+					parent_dir.display(),
+					generated,
+					total_size
+				);
+				if opts.stop_on_low_space {
+					STOP_SIGNAL.store(true, Ordering::SeqCst);
+				}
+				break;
+			}
+		}
+	}
+
+	writer.flush().await?;
+	writer.get_ref().sync_all().await?;
+
+	Ok(written)
+}
+
+async fn verify_contents_async(filename: &Path, expected: &[u8]) -> std::io::Result<()> {
+	let mut file = tokio::fs::File::open(filename).await?;
+	let mut contents = Vec::with_capacity(expected.len());
+	file.read_to_end(&mut contents).await?;
+
+	if contents != expected {
+		return Err(std::io::Error::new(
+			std::io::ErrorKind::InvalidData,
+			format!(
+				"Verification mismatch for {}: read {} bytes, expected {} bytes",
+				filename.display(),
+				contents.len(),
+				expected.len()
+			),
+		));
+	}
+
+	Ok(())
+}
+
+async fn read_tracked_file_async() -> std::io::Result<()> {
+	let candidate = {
+		let files = CREATED_FILES.lock().unwrap();
+		if files.is_empty() {
+			None
+		} else {
+			let idx = rand::rng().random_range(0..files.len());
+			files.iter().nth(idx).map(|(name, expected)| (name.clone(), expected.clone()))
+		}
+	};
+
+	let Some((filename, expected)) = candidate else {
+		tokio::time::sleep(NO_TRACKED_FILES_BACKOFF).await;
+		return Ok(());
+	};
+
+	// Streamed files don't keep their full content in memory, so there's nothing to
+	// compare a read-back against. Back off the same as an empty CREATED_FILES map,
+	// since otherwise a run with streamed writes only would spin readers on the lock.
+	let Some(expected) = expected else {
+		tokio::time::sleep(NO_TRACKED_FILES_BACKOFF).await;
+		return Ok(());
+	};
+
+	let mut file = match tokio::fs::File::open(&filename).await {
+		Ok(file) => file,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+		Err(e) => return Err(e),
+	};
+
+	let mut contents = Vec::with_capacity(expected.len());
+	file.read_to_end(&mut contents).await?;
+	println!("Read back {} bytes from {}", contents.len(), filename.display());
+
+	if contents != *expected {
+		eprintln!(
+			"Error: read-back mismatch for {}: read {} bytes, expected {} bytes",
+			filename.display(),
+			contents.len(),
+			expected.len()
+		);
+	}
+
+	Ok(())
+}
+
+async fn disk_thrash_async(
+	parent_dir: &PathBuf,
+	buffer: Arc<Vec<u8>>,
+	opts: WriteOptions,
+) -> std::io::Result<()> {
+	let pending_bytes = opts.file_size.map_or(buffer.len() as u64, |FileSize(total)| total);
+	if !has_room_async(parent_dir, opts.min_free, pending_bytes).await? {
+		println!("Low free space on {}, refusing to write", parent_dir.display());
+		if opts.stop_on_low_space {
+			STOP_SIGNAL.store(true, Ordering::SeqCst);
+		}
+		return Ok(());
+	}
+
+	let filename = parent_dir.join(format!("{}.tmp", Uuid::new_v4()));
+
+	{
+		// Content isn't known yet; filled in below once it's written (streamed
+		// files stay `None`, since their full content isn't kept in memory).
+		CREATED_FILES.lock().unwrap().insert(filename.clone(), None);
+	}
 
-	let shared_buffer = Arc::new(buffer);
+	let file = tokio::fs::File::create(&filename).await?;
+	println!("Writing to file: {}", filename.display());
+
+	if buffer.is_empty() {
+		return Err(std::io::Error::new(
+			std::io::ErrorKind::InvalidInput,
+			"Buffer is empty",
+		));
+	}
+
+	let (written, verify_result) = if let Some(FileSize(total_size)) = opts.file_size {
+		if opts.verify {
+			println!("Verification is not supported with --file-size streaming, skipping");
+		}
+
+		let op_start = Instant::now();
+		let written = write_streamed_async(file, total_size, buffer.len(), parent_dir, &opts).await?;
+		METRICS.record(written, op_start.elapsed());
+		println!("File sync complete for: {}", filename.display());
+		(written, Ok(()))
+	} else {
+		let mut file = file;
+
+		// Compression runs on this task's own thread per iteration (sender-pays),
+		// mirroring the blocking path rather than precomputing on the shared buffer.
+		let to_write: std::borrow::Cow<[u8]> = match opts.compress {
+			Some(Compress::Lz4) => std::borrow::Cow::Owned(lz4_compress(&buffer)?),
+			None => std::borrow::Cow::Borrowed(buffer.as_slice()),
+		};
+
+		let op_start = Instant::now();
+		file.write_all(&to_write).await?;
+		println!("Finished writing to file: {}", filename.display());
+
+		file.sync_all().await?;
+		METRICS.record(to_write.len() as u64, op_start.elapsed());
+		println!("File sync complete for: {}", filename.display());
+
+		// A mismatch is the expected-to-happen case this tool exists to surface, not
+		// an exceptional one, so it must not skip the cleanup below (else the file
+		// and its CREATED_FILES entry leak for the rest of the run).
+		let verify_result = if opts.verify {
+			verify_contents_async(&filename, &to_write).await
+		} else {
+			Ok(())
+		};
+		if let Err(e) = &verify_result {
+			eprintln!("Error: {}", e);
+		}
+
+		let written = to_write.len() as u64;
+		if let Some(expected) = CREATED_FILES.lock().unwrap().get_mut(&filename) {
+			*expected = Some(Arc::new(to_write.into_owned()));
+		}
+
+		(written, verify_result)
+	};
+
+	let metadata = tokio::fs::metadata(&filename).await?;
+	if metadata.len() != written {
+		eprintln!(
+			"Error: File did not write the expected size: {} bytes written, expected {} bytes.",
+			metadata.len(),
+			written
+		);
+	}
+
+	tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+	tokio::fs::remove_file(&filename).await?;
+
+	{
+		CREATED_FILES.lock().unwrap().remove(&filename);
+	}
+
+	verify_result?;
+
+	Ok(())
+}
+
+fn run_blocking(args: Args) {
+	let size = args.buffer_size * 1024 * 1024;
+	let shared_buffer = Arc::new(generate_buffer(args.pattern, size));
 	let num_threads = num_cpus::get() - 2;
 
 	println!("Spawning {} threads", num_threads);
 
+	let opts = WriteOptions::from_args(&args);
 	let mut handles = Vec::new();
 
 	for id in 0..num_threads {
 		let parent_dir = args.parent_dir.clone();
 		let buffer = Arc::clone(&shared_buffer);
+		let read_ratio = args.read_ratio;
 
 		handles.push(thread::spawn(move || {
 			println!("Thread {} started", id);
 			while !STOP_SIGNAL.load(Ordering::SeqCst) {
-				if let Err(e) = disk_thrash(&parent_dir, &buffer) {
+				let is_read = read_ratio > 0.0 && rand::rng().random::<f64>() < read_ratio;
+
+				let result = if is_read {
+					read_tracked_file()
+				} else {
+					disk_thrash(&parent_dir, &buffer, opts)
+				};
+
+				if let Err(e) = result {
 					eprintln!("Thread {} error: {}", id, e);
 				}
 			}
@@ -117,13 +942,234 @@ fn main() {
 	for h in handles {
 		h.join().unwrap();
 	}
+}
+
+async fn run_async(args: Args) {
+	let size = args.buffer_size * 1024 * 1024;
+	let shared_buffer = Arc::new(generate_buffer(args.pattern, size));
+	let semaphore = Arc::new(Semaphore::new(args.concurrency));
+
+	println!("Running async with concurrency {}", args.concurrency);
+
+	let opts = WriteOptions::from_args(&args);
+	let mut handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+
+	while !STOP_SIGNAL.load(Ordering::SeqCst) {
+		// Drop handles for tasks that have already completed, since a task is spawned
+		// per iteration (bounded by the semaphore, not by total count) and this loop
+		// can run for a very long time.
+		handles.retain(|h| !h.is_finished());
+
+		let permit = Arc::clone(&semaphore).acquire_owned().await.unwrap();
+		let parent_dir = args.parent_dir.clone();
+		let buffer = Arc::clone(&shared_buffer);
+		let read_ratio = args.read_ratio;
+
+		handles.push(tokio::spawn(async move {
+			let _permit = permit;
+			let is_read = read_ratio > 0.0 && rand::rng().random::<f64>() < read_ratio;
+
+			let result = if is_read {
+				read_tracked_file_async().await
+			} else {
+				disk_thrash_async(&parent_dir, buffer, opts).await
+			};
+
+			if let Err(e) = result {
+				eprintln!("Task error: {}", e);
+			}
+		}));
+	}
+
+	for h in handles {
+		let _ = h.await;
+	}
+}
+
+fn main() {
+	let args = Args::parse();
+
+	ctrlc::set_handler(|| {
+		println!("CTRL+C received, stopping...");
+		STOP_SIGNAL.store(true, Ordering::SeqCst);
+	}).expect("Failed to set Ctrl-C handler");
+
+	let reporter = spawn_reporter(args.report_interval);
+
+	match args.mode {
+		Mode::Blocking => run_blocking(args),
+		Mode::Async => {
+			let runtime = tokio::runtime::Builder::new_multi_thread()
+				.enable_all()
+				.build()
+				.expect("Failed to build tokio runtime");
+			runtime.block_on(run_async(args));
+		}
+	}
 
 	println!("Cleaning up remaining files...");
 
-	let remaining: Vec<_> = CREATED_FILES.lock().unwrap().drain().collect();
+	let remaining: Vec<_> = CREATED_FILES.lock().unwrap().drain().map(|(path, _)| path).collect();
 	for path in remaining {
 		let _ = std::fs::remove_file(path);
 	}
 
+	STOP_SIGNAL.store(true, Ordering::SeqCst);
+	reporter.join().unwrap();
+	print_final_summary();
+
 	println!("Done.");
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_byte_size_parses_units() {
+		assert_eq!(parse_byte_size("512").unwrap(), 512);
+		assert_eq!(parse_byte_size("512b").unwrap(), 512);
+		assert_eq!(parse_byte_size("500KB").unwrap(), 500 * 1024);
+		assert_eq!(parse_byte_size("10GB").unwrap(), 10 * 1024 * 1024 * 1024);
+		assert_eq!(parse_byte_size("2TB").unwrap(), 2u64 * 1024 * 1024 * 1024 * 1024);
+		assert_eq!(parse_byte_size(" 10 mb ").unwrap(), 10 * 1024 * 1024);
+	}
+
+	#[test]
+	fn parse_byte_size_rejects_garbage() {
+		assert!(parse_byte_size("not-a-size").is_err());
+		assert!(parse_byte_size("").is_err());
+	}
+
+	#[test]
+	fn min_free_from_str_parses_percent_boundaries() {
+		assert!(matches!(MinFree::from_str("0%").unwrap(), MinFree::Percent(p) if p == 0.0));
+		assert!(matches!(MinFree::from_str("100%").unwrap(), MinFree::Percent(p) if p == 100.0));
+		assert!(matches!(MinFree::from_str("5%").unwrap(), MinFree::Percent(p) if p == 5.0));
+	}
+
+	#[test]
+	fn min_free_from_str_rejects_out_of_range_percent() {
+		assert!(MinFree::from_str("101%").is_err());
+		assert!(MinFree::from_str("-1%").is_err());
+	}
+
+	#[test]
+	fn min_free_from_str_parses_bytes() {
+		assert!(matches!(MinFree::from_str("10MB").unwrap(), MinFree::Bytes(b) if b == 10 * 1024 * 1024));
+	}
+
+	#[test]
+	fn meets_min_free_bytes_is_inclusive_boundary() {
+		assert!(meets_min_free(100, 1000, MinFree::Bytes(100)));
+		assert!(!meets_min_free(99, 1000, MinFree::Bytes(100)));
+	}
+
+	#[test]
+	fn meets_min_free_percent_is_inclusive_boundary() {
+		assert!(meets_min_free(50, 1000, MinFree::Percent(5.0)));
+		assert!(!meets_min_free(49, 1000, MinFree::Percent(5.0)));
+	}
+
+	#[test]
+	fn meets_min_free_percent_handles_zero_total() {
+		// A zero-size filesystem can't satisfy any positive percentage requirement.
+		assert!(!meets_min_free(0, 0, MinFree::Percent(0.1)));
+	}
+
+	#[test]
+	fn percentile_is_zero_with_no_samples() {
+		let metrics = Metrics::new();
+		assert_eq!(metrics.percentile(50.0), 0);
+		assert_eq!(metrics.percentile(99.0), 0);
+	}
+
+	#[test]
+	fn percentile_buckets_by_power_of_two() {
+		let metrics = Metrics::new();
+		// 2us and 3us share the [2us, 4us) bucket, reported as its 2us lower bound.
+		metrics.record(0, Duration::from_micros(2));
+		metrics.record(0, Duration::from_micros(3));
+		assert_eq!(metrics.percentile(50.0), 2);
+		assert_eq!(metrics.percentile(100.0), 2);
+	}
+
+	#[test]
+	fn percentile_picks_bucket_covering_target_rank() {
+		let metrics = Metrics::new();
+		// 9 fast samples in the 1us bucket, 1 slow sample in the 8us bucket: p50
+		// falls inside the fast bucket, p99 needs the slow one.
+		for _ in 0..9 {
+			metrics.record(0, Duration::from_micros(1));
+		}
+		metrics.record(0, Duration::from_micros(8));
+		assert_eq!(metrics.percentile(50.0), 1);
+		assert_eq!(metrics.percentile(99.0), 8);
+	}
+
+	#[test]
+	fn percentile_caps_at_last_bucket_for_huge_latencies() {
+		let metrics = Metrics::new();
+		metrics.record(0, Duration::from_secs(10_000));
+		assert_eq!(metrics.percentile(100.0), 1u64 << (NUM_LATENCY_BUCKETS - 1));
+	}
+
+	#[test]
+	fn fill_spare_capacity_zero_pattern() {
+		let mut chunk = Vec::new();
+		fill_spare_capacity(&mut chunk, 5, Pattern::Zero, &mut rand::rng());
+		assert_eq!(chunk, vec![0u8; 5]);
+	}
+
+	#[test]
+	fn fill_spare_capacity_random_pattern_fills_requested_length() {
+		let mut chunk = Vec::new();
+		fill_spare_capacity(&mut chunk, 64, Pattern::Random, &mut rand::rng());
+		assert_eq!(chunk.len(), 64);
+	}
+
+	#[test]
+	fn fill_spare_capacity_text_pattern_cycles_unit() {
+		const UNIT: &[u8] = b"The quick brown fox jumps over the lazy dog. ";
+		let mut chunk = Vec::new();
+
+		// One byte short of a full unit, exactly a full unit, and one byte into a
+		// second cycle, to exercise the `.cycle()` wraparound boundary.
+		for len in [UNIT.len() - 1, UNIT.len(), UNIT.len() + 1] {
+			fill_spare_capacity(&mut chunk, len, Pattern::Text, &mut rand::rng());
+			let expected: Vec<u8> = UNIT.iter().cycle().take(len).copied().collect();
+			assert_eq!(chunk, expected, "mismatch at len={}", len);
+		}
+	}
+
+	#[test]
+	fn fill_spare_capacity_repeating_pattern_cycles_unit() {
+		const UNIT: &[u8] = &[0xDE, 0xAD, 0xBE, 0xEF];
+		let mut chunk = Vec::new();
+
+		for len in [UNIT.len() - 1, UNIT.len(), UNIT.len() + 1] {
+			fill_spare_capacity(&mut chunk, len, Pattern::Repeating, &mut rand::rng());
+			let expected: Vec<u8> = UNIT.iter().cycle().take(len).copied().collect();
+			assert_eq!(chunk, expected, "mismatch at len={}", len);
+		}
+	}
+
+	#[test]
+	fn fill_spare_capacity_reuses_buffer_across_shrinking_len() {
+		let mut chunk = Vec::new();
+		fill_spare_capacity(&mut chunk, 32, Pattern::Zero, &mut rand::rng());
+		assert_eq!(chunk.len(), 32);
+
+		// A later call with a smaller len must not leave stale bytes from the
+		// larger previous fill past the new length.
+		fill_spare_capacity(&mut chunk, 4, Pattern::Repeating, &mut rand::rng());
+		assert_eq!(chunk, vec![0xDE, 0xAD, 0xBE, 0xEF]);
+	}
+
+	#[test]
+	fn fill_spare_capacity_handles_zero_len() {
+		let mut chunk = Vec::new();
+		fill_spare_capacity(&mut chunk, 0, Pattern::Text, &mut rand::rng());
+		assert!(chunk.is_empty());
+	}
+}